@@ -0,0 +1,158 @@
+use crate::pattern::PatternSet;
+use git2::Repository;
+use std::path::{Path, PathBuf};
+
+/// The patterns declared by one ignore file, scoped to the directory it was
+/// discovered in: anchored patterns inside the file resolve relative to
+/// this root, not the overall repo root.
+struct IgnoreScope {
+    root: PathBuf,
+    patterns: PatternSet,
+}
+
+/// Every ignore file discovered across a repository tree, ordered from
+/// lowest to highest precedence so later scopes can override earlier ones,
+/// mirroring the order git itself applies `core.excludesFile`,
+/// `.git/info/exclude` and per-directory `.gitignore` files.
+pub struct IgnoreRegistry {
+    scopes: Vec<IgnoreScope>,
+}
+
+impl IgnoreRegistry {
+    pub fn new() -> Self {
+        IgnoreRegistry { scopes: Vec::new() }
+    }
+
+    /// Discovers `core.excludesFile`, `.git/info/exclude`, `.hgignore` and
+    /// every `.gitignore` beneath `repo_root`, in git's own precedence
+    /// order: global excludes first, then per-directory `.gitignore`s
+    /// from the root down to the leaves.
+    pub fn discover(repo_root: &Path) -> IgnoreRegistry {
+        let mut registry = IgnoreRegistry::new();
+
+        if let Some(path) = core_excludes_file(repo_root) {
+            registry.push_file(repo_root, &path);
+        }
+        registry.push_file(
+            repo_root,
+            &repo_root.join(".git").join("info").join("exclude"),
+        );
+        registry.push_file(repo_root, &repo_root.join(".hgignore"));
+
+        for dir in directories_root_to_leaf(repo_root) {
+            let gitignore = dir.join(".gitignore");
+            registry.push_file(&dir, &gitignore);
+        }
+
+        registry
+    }
+
+    /// Registers `path`'s patterns (if it exists and parses) as a scope
+    /// rooted at `root`.
+    pub fn push_file(&mut self, root: &Path, path: &Path) {
+        if let Some(patterns) = load_ignore_file(path) {
+            self.scopes.push(IgnoreScope {
+                root: root.to_path_buf(),
+                patterns,
+            });
+        }
+    }
+
+    /// Returns `Some(true)` if the last matching scope ignores `path`,
+    /// `Some(false)` if it whitelists it, or `None` if nothing matched.
+    pub fn evaluate(&self, path: &Path) -> Option<bool> {
+        let mut decision = None;
+        for scope in &self.scopes {
+            if let Ok(relative) = path.strip_prefix(&scope.root) {
+                if let Some(result) = scope.patterns.evaluate(relative) {
+                    decision = Some(result);
+                }
+            }
+        }
+        decision
+    }
+}
+
+fn load_ignore_file(path: &Path) -> Option<PatternSet> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let lines: Vec<&str> = content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect();
+    let patterns = PatternSet::from_patterns(&lines).ok()?;
+    if patterns.is_empty() {
+        return None;
+    }
+    Some(patterns)
+}
+
+fn core_excludes_file(repo_root: &Path) -> Option<PathBuf> {
+    let repo = Repository::discover(repo_root).ok()?;
+    let config = repo.config().ok()?;
+    let raw = config.get_string("core.excludesFile").ok()?;
+    Some(expand_tilde(&raw))
+}
+
+fn expand_tilde(raw: &str) -> PathBuf {
+    if raw == "~" {
+        return std::env::var_os("HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(raw));
+    }
+    match raw.strip_prefix("~/") {
+        Some(rest) => std::env::var_os("HOME")
+            .map(|home| PathBuf::from(home).join(rest))
+            .unwrap_or_else(|| PathBuf::from(raw)),
+        None => PathBuf::from(raw),
+    }
+}
+
+/// Directory names never worth descending into while hunting for nested
+/// `.gitignore` files: VCS internals and the build/dependency directories a
+/// real-world repo's own `.gitignore` would already exclude anyway. Skipping
+/// them up front keeps this discovery walk from doubling the cost of the
+/// main traversal on large repos (`.git/objects`, `node_modules`, `target`).
+const SKIP_DIRS: [&str; 3] = [".git", "node_modules", "target"];
+
+fn directories_root_to_leaf(repo_root: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = ignore::WalkBuilder::new(repo_root)
+        .hidden(false)
+        .parents(false)
+        .ignore(false)
+        .git_ignore(false)
+        .git_global(false)
+        .git_exclude(false)
+        .filter_entry(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| !SKIP_DIRS.contains(&name))
+        })
+        .build()
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_some_and(|ft| ft.is_dir()))
+        .map(|e| e.path().to_path_buf())
+        .collect();
+    dirs.sort_by_key(|d| d.components().count());
+    dirs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expand_tilde_resolves_home_relative_paths() {
+        std::env::set_var("HOME", "/home/example");
+        assert_eq!(
+            expand_tilde("~/.gitignore_global"),
+            PathBuf::from("/home/example/.gitignore_global")
+        );
+        assert_eq!(expand_tilde("~"), PathBuf::from("/home/example"));
+        assert_eq!(
+            expand_tilde("/etc/gitignore"),
+            PathBuf::from("/etc/gitignore")
+        );
+    }
+}
@@ -1,9 +1,13 @@
 mod default_ignore;
+mod ignore_discovery;
+mod matcher;
+mod pattern;
 
 use default_ignore::DefaultIgnore;
 use git2::Repository;
-use globset::{Glob, GlobSetBuilder};
 use ignore::WalkBuilder;
+use ignore_discovery::IgnoreRegistry;
+use matcher::Matcher;
 use std::fs::File;
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
@@ -28,10 +32,27 @@ struct Cli {
     #[structopt(long, use_delimiter = true)]
     ignore_dirs: Option<Vec<String>>,
 
-    /// Files to include, separated by commas (exclusive with --ignore-files and --ignore-dirs)
-    #[structopt(long, use_delimiter = true, conflicts_with_all = &["ignore_files", "ignore_dirs"])]
+    /// Files or directories to force-include, separated by commas. An exact
+    /// path/filename wins over any exclusion; a glob-style entry (e.g. `src/*.rs`)
+    /// only overrides the default ignore-file patterns and --ignore-files, not
+    /// ignored directories (default or --ignore-dirs) and not files excluded
+    /// by a discovered .gitignore.
+    #[structopt(long, use_delimiter = true)]
     include_files: Option<Vec<String>>,
 
+    /// Skip all discovered ignore files, both VCS (.gitignore, .git/info/exclude,
+    /// core.excludesFile, .hgignore) and generic (.ignore)
+    #[structopt(long)]
+    no_ignore: bool,
+
+    /// Skip auto-loading of .gitignore, .git/info/exclude, core.excludesFile and .hgignore
+    #[structopt(long)]
+    no_vcs_ignore: bool,
+
+    /// Disable the built-in default ignore list
+    #[structopt(long)]
+    no_default_ignore: bool,
+
     /// Output file
     #[structopt(parse(from_os_str))]
     output: Option<PathBuf>,
@@ -69,27 +90,49 @@ fn main() -> io::Result<()> {
         None
     };
 
-    for entry in WalkBuilder::new(input_path)
-        .add_custom_ignore_filename(".ignore")
-        .build()
-        .filter_map(Result::ok)
-        .filter(|e| e.file_type().map_or(false, |ft| ft.is_file()))
-    {
-        let path = entry.path();
-        if should_include(path, &args, &DefaultIgnore::default()) {
-            match std::fs::read_to_string(path) {
-                Ok(content) => {
-                    writeln!(
-                        output_file,
-                        "\n\n// File: {}\n\n{}",
-                        path.display(),
-                        content
-                    )?;
-                }
-                Err(e) => {
-                    write_error_to_log(&mut error_log_file, path, e)?;
-                    continue;
-                }
+    let skip_vcs_ignore = args.no_ignore || args.no_vcs_ignore;
+
+    let ignore_registry = if skip_vcs_ignore {
+        IgnoreRegistry::new()
+    } else {
+        IgnoreRegistry::discover(&input_path)
+    };
+
+    let default_ignore = if args.no_default_ignore {
+        DefaultIgnore {
+            ignore_files: Vec::new(),
+            ignore_dirs: Vec::new(),
+        }
+    } else {
+        DefaultIgnore::default()
+    };
+
+    let matcher = Matcher::build(
+        args.ignore_dirs.as_deref(),
+        args.ignore_files.as_deref(),
+        args.include_files.as_deref(),
+        &default_ignore,
+    )
+    .map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid pattern: {}", e),
+        )
+    })?;
+
+    for path in collect_included_files(&input_path, args.no_ignore, &matcher, &ignore_registry) {
+        match std::fs::read_to_string(&path) {
+            Ok(content) => {
+                writeln!(
+                    output_file,
+                    "\n\n// File: {}\n\n{}",
+                    path.display(),
+                    content
+                )?;
+            }
+            Err(e) => {
+                write_error_to_log(&mut error_log_file, &path, e)?;
+                continue;
             }
         }
     }
@@ -97,6 +140,40 @@ fn main() -> io::Result<()> {
     Ok(())
 }
 
+/// Walks `input_path` and returns every file the matcher/registry pair
+/// decides to include, with the same VCS-ignore wiring `main` uses. Split
+/// out from `main` so tests can drive the real walk end-to-end instead of
+/// calling `Matcher::should_include` against a hand-built registry.
+fn collect_included_files(
+    input_path: &Path,
+    no_ignore: bool,
+    matcher: &Matcher,
+    ignore_registry: &IgnoreRegistry,
+) -> Vec<PathBuf> {
+    let mut walker = WalkBuilder::new(input_path);
+    if no_ignore {
+        walker.ignore(false);
+    } else {
+        walker.add_custom_ignore_filename(".ignore");
+    }
+    // VCS-ignore decisions are made exclusively by `ignore_registry`/`matcher`
+    // below, so --include-files can see past (and override) them; the
+    // `ignore` crate's own .gitignore/.git/info/exclude handling must never
+    // run too, or it would drop those paths before our override logic does.
+    walker
+        .git_ignore(false)
+        .git_global(false)
+        .git_exclude(false);
+
+    walker
+        .build()
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().map_or(false, |ft| ft.is_file()))
+        .map(|e| e.path().to_path_buf())
+        .filter(|path| matcher.should_include(path, input_path, ignore_registry))
+        .collect()
+}
+
 fn write_error_to_log(
     error_log_file: &mut Option<File>,
     path: &Path,
@@ -112,45 +189,6 @@ fn write_error_to_log(
     })
 }
 
-// Function to determine if a file should be included based on the arguments
-fn should_include(path: &Path, args: &Cli, config: &DefaultIgnore) -> bool {
-    let mut ignore_files: Vec<&str> = config.ignore_files.iter().map(String::as_str).collect();
-    let mut ignore_dirs: Vec<&str> = config.ignore_dirs.iter().map(String::as_str).collect();
-
-    if let Some(user_ignore_files) = &args.ignore_files {
-        ignore_files.extend(user_ignore_files.iter().map(String::as_str));
-    }
-
-    if let Some(user_ignore_dirs) = &args.ignore_dirs {
-        ignore_dirs.extend(user_ignore_dirs.iter().map(String::as_str));
-    }
-
-    let mut glob_builder = GlobSetBuilder::new();
-    for pattern in &ignore_files {
-        glob_builder.add(Glob::new(pattern).unwrap());
-    }
-    let glob_set = glob_builder.build().unwrap();
-
-    if let Some(include_files) = &args.include_files {
-        return include_files.iter().any(|f| path.ends_with(f));
-    }
-
-    let path_str = path.to_str().unwrap_or_default();
-
-    if glob_set.is_match(path_str) || ignore_files.iter().any(|&f| path.ends_with(f)) {
-        return false;
-    }
-
-    if ignore_dirs
-        .iter()
-        .any(|&d| path.components().any(|comp| comp.as_os_str() == d))
-    {
-        return false;
-    }
-
-    true
-}
-
 fn is_github_url(path: &Path) -> bool {
     path.to_str()
         .map_or(false, |s| s.starts_with("https://github.com/"))
@@ -187,141 +225,254 @@ mod tests {
         };
     }
 
+    fn test_root() -> PathBuf {
+        PathBuf::from("input")
+    }
+
+    fn empty_registry() -> IgnoreRegistry {
+        IgnoreRegistry::new()
+    }
+
+    fn build_matcher(
+        ignore_dirs: Option<Vec<String>>,
+        ignore_files: Option<Vec<String>>,
+        include_files: Option<Vec<String>>,
+    ) -> Matcher {
+        Matcher::build(
+            ignore_dirs.as_deref(),
+            ignore_files.as_deref(),
+            include_files.as_deref(),
+            &default_ignore_files(),
+        )
+        .unwrap()
+    }
+
     #[test]
     fn test_should_include_no_ignore_no_include() {
-        let args = Cli {
-            input: PathBuf::from("input"),
-            ignore_files: None,
-            ignore_dirs: None,
-            include_files: None,
-            output: Some(PathBuf::from("output.txt")),
-            error_log: false,
-        };
+        let matcher = build_matcher(None, None, None);
 
         let path = PathBuf::from("input/test_file.txt");
-        assert!(should_include(&path, &args, &default_ignore_files()));
+        assert!(matcher.should_include(&path, &test_root(), &empty_registry()));
         let lock_path = PathBuf::from("input/Cargo.lock");
-        assert!(!should_include(&lock_path, &args, &default_ignore_files()));
+        assert!(!matcher.should_include(&lock_path, &test_root(), &empty_registry()));
     }
 
     #[test]
     fn test_should_include_with_ignore_files() {
-        let args = Cli {
-            input: PathBuf::from("input"),
-            ignore_files: Some(vec!["test_file.txt".to_string()]),
-            ignore_dirs: None,
-            include_files: None,
-            output: Some(PathBuf::from("output.txt")),
-            error_log: false,
-        };
+        let matcher = build_matcher(None, Some(vec!["test_file.txt".to_string()]), None);
 
         let path = PathBuf::from("input/test_file.txt");
-        assert!(!should_include(&path, &args, &default_ignore_files()));
+        assert!(!matcher.should_include(&path, &test_root(), &empty_registry()));
 
         let other_path = PathBuf::from("input/other_file.txt");
-        assert!(should_include(&other_path, &args, &default_ignore_files()));
+        assert!(matcher.should_include(&other_path, &test_root(), &empty_registry()));
     }
 
     #[test]
     fn test_should_include_with_ignore_dirs() {
-        let args = Cli {
-            input: PathBuf::from("input"),
-            ignore_files: None,
-            ignore_dirs: Some(vec!["ignore_dir".to_string()]),
-            include_files: None,
-            output: Some(PathBuf::from("output.txt")),
-            error_log: false,
-        };
+        let matcher = build_matcher(Some(vec!["ignore_dir".to_string()]), None, None);
 
         let path = PathBuf::from("input/ignore_dir/test_file.txt");
-        assert!(!should_include(&path, &args, &default_ignore_files()));
+        assert!(!matcher.should_include(&path, &test_root(), &empty_registry()));
 
         let other_path = PathBuf::from("input/other_dir/test_file.txt");
-        assert!(should_include(&other_path, &args, &default_ignore_files()));
+        assert!(matcher.should_include(&other_path, &test_root(), &empty_registry()));
     }
 
     #[test]
-    fn test_should_include_with_include_files() {
-        let args = Cli {
-            input: PathBuf::from("input"),
-            ignore_files: None,
-            ignore_dirs: None,
-            include_files: Some(vec!["include_file.txt".to_string()]),
-            output: Some(PathBuf::from("output.txt")),
-            error_log: false,
-        };
-
-        let path = PathBuf::from("input/include_file.txt");
-        assert!(should_include(&path, &args, &default_ignore_files()));
+    fn test_should_include_with_include_files_overrides_ignore() {
+        let matcher = build_matcher(
+            None,
+            Some(vec!["secret.txt".to_string()]),
+            Some(vec!["secret.txt".to_string()]),
+        );
+
+        // Explicitly included even though it is also ignored.
+        let path = PathBuf::from("input/secret.txt");
+        assert!(matcher.should_include(&path, &test_root(), &empty_registry()));
+
+        // Everything else still goes through the normal ignore rules.
+        let lock_path = PathBuf::from("input/Cargo.lock");
+        assert!(!matcher.should_include(&lock_path, &test_root(), &empty_registry()));
 
         let other_path = PathBuf::from("input/other_file.txt");
-        assert!(!should_include(&other_path, &args, &default_ignore_files()));
+        assert!(matcher.should_include(&other_path, &test_root(), &empty_registry()));
+    }
+
+    #[test]
+    fn test_should_include_with_glob_include_respects_discovered_ignore() {
+        let dir = TempDir::new("repo2file-test").unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "secret.lock\n").unwrap();
+
+        let registry = IgnoreRegistry::discover(dir.path());
+        let matcher = build_matcher(None, None, Some(vec!["*.lock".to_string()]));
+
+        // The glob include resurrects a file the default ignore list would
+        // otherwise drop...
+        let other_lock = dir.path().join("other.lock");
+        assert!(matcher.should_include(&other_lock, dir.path(), &registry));
+
+        // ...but not one individually excluded by a discovered .gitignore.
+        let secret_lock = dir.path().join("secret.lock");
+        assert!(!matcher.should_include(&secret_lock, dir.path(), &registry));
+    }
+
+    #[test]
+    fn test_collect_included_files_lets_include_files_resurrect_real_gitignore_entry() {
+        let dir = TempDir::new("repo2file-e2e").unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "secret.rs\n").unwrap();
+        std::fs::write(dir.path().join("secret.rs"), "fn secret() {}").unwrap();
+        std::fs::write(dir.path().join("main.rs"), "fn main() {}").unwrap();
+
+        let registry = IgnoreRegistry::discover(dir.path());
+
+        // Without an override, the real .gitignore on disk excludes secret.rs,
+        // proven by actually running WalkBuilder, not a hand-built registry.
+        let matcher = build_matcher(None, None, None);
+        let files = collect_included_files(dir.path(), false, &matcher, &registry);
+        assert!(!files.iter().any(|p| p.ends_with("secret.rs")));
+        assert!(files.iter().any(|p| p.ends_with("main.rs")));
+
+        // --include-files resurrects it even though the walk's own
+        // .gitignore handling would otherwise have dropped it already.
+        let matcher = build_matcher(None, None, Some(vec!["secret.rs".to_string()]));
+        let files = collect_included_files(dir.path(), false, &matcher, &registry);
+        assert!(files.iter().any(|p| p.ends_with("secret.rs")));
+    }
+
+    #[test]
+    fn test_should_include_discovered_whitelist_beats_default_ignore_list() {
+        let dir = TempDir::new("repo2file-precedence").unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "*.lock\n!package.lock\n").unwrap();
+
+        let registry = IgnoreRegistry::discover(dir.path());
+        let matcher = build_matcher(None, None, None);
+
+        // The repo's own .gitignore whitelists package.lock; the tool's
+        // default ignore list (which also matches *.lock) must not override
+        // that decision.
+        let package_lock = dir.path().join("package.lock");
+        assert!(matcher.should_include(&package_lock, dir.path(), &registry));
+
+        // Every other *.lock file is still excluded, by that same .gitignore's
+        // own `*.lock` rule.
+        let other_lock = dir.path().join("other.lock");
+        assert!(!matcher.should_include(&other_lock, dir.path(), &registry));
     }
 
     #[test]
     fn test_should_include_with_ignore_and_include() {
-        let args = Cli {
-            input: PathBuf::from("input"),
-            ignore_files: Some(vec!["test_file.txt".to_string()]),
-            ignore_dirs: Some(vec!["ignore_dir".to_string()]),
-            include_files: None,
-            output: Some(PathBuf::from("output.txt")),
-            error_log: false,
-        };
+        let matcher = build_matcher(
+            Some(vec!["ignore_dir".to_string()]),
+            Some(vec!["test_file.txt".to_string()]),
+            None,
+        );
 
         let path = PathBuf::from("input/test_file.txt");
-        assert!(!should_include(&path, &args, &default_ignore_files()));
+        assert!(!matcher.should_include(&path, &test_root(), &empty_registry()));
 
         let dir_path = PathBuf::from("input/ignore_dir/test_file.txt");
-        assert!(!should_include(&dir_path, &args, &default_ignore_files()));
+        assert!(!matcher.should_include(&dir_path, &test_root(), &empty_registry()));
 
         let other_path = PathBuf::from("input/other_file.txt");
-        assert!(should_include(&other_path, &args, &default_ignore_files()));
+        assert!(matcher.should_include(&other_path, &test_root(), &empty_registry()));
     }
 
     #[test]
     fn test_should_include_with_multiple_ignore_files() {
-        let args = Cli {
-            input: PathBuf::from("input"),
-            ignore_files: Some(vec![
+        let matcher = build_matcher(
+            None,
+            Some(vec![
                 "test_file.txt".to_string(),
                 "ignore_file.txt".to_string(),
             ]),
-            ignore_dirs: None,
-            include_files: None,
-            output: Some(PathBuf::from("output.txt")),
-            error_log: false,
-        };
+            None,
+        );
 
         let path = PathBuf::from("input/test_file.txt");
-        assert!(!should_include(&path, &args, &default_ignore_files()));
+        assert!(!matcher.should_include(&path, &test_root(), &empty_registry()));
 
         let path = PathBuf::from("input/ignore_file.txt");
-        assert!(!should_include(&path, &args, &default_ignore_files()));
+        assert!(!matcher.should_include(&path, &test_root(), &empty_registry()));
 
         let path = PathBuf::from("input/valid_file.txt");
-        assert!(should_include(&path, &args, &default_ignore_files()));
+        assert!(matcher.should_include(&path, &test_root(), &empty_registry()));
     }
 
     #[test]
     fn test_should_include_with_multiple_ignore_dirs() {
-        let args = Cli {
-            input: PathBuf::from("input"),
-            ignore_files: None,
-            ignore_dirs: Some(vec!["ignore_dir1".to_string(), "ignore_dir2".to_string()]),
-            include_files: None,
-            output: Some(PathBuf::from("output.txt")),
-            error_log: false,
-        };
+        let matcher = build_matcher(
+            Some(vec!["ignore_dir1".to_string(), "ignore_dir2".to_string()]),
+            None,
+            None,
+        );
 
         let path1 = PathBuf::from("input/ignore_dir1/test_file.txt");
-        assert!(!should_include(&path1, &args, &default_ignore_files()));
+        assert!(!matcher.should_include(&path1, &test_root(), &empty_registry()));
 
         let path2 = PathBuf::from("input/ignore_dir2/test_file.txt");
-        assert!(!should_include(&path2, &args, &default_ignore_files()));
+        assert!(!matcher.should_include(&path2, &test_root(), &empty_registry()));
 
         let valid_path = PathBuf::from("input/valid_dir/test_file.txt");
-        assert!(should_include(&valid_path, &args, &default_ignore_files()));
+        assert!(matcher.should_include(&valid_path, &test_root(), &empty_registry()));
+    }
+
+    #[test]
+    fn test_no_vcs_ignore_flag_skips_discovered_ignore_but_keeps_dot_ignore_files() {
+        let dir = TempDir::new("repo2file-no-vcs-ignore").unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "secret.txt\n").unwrap();
+        std::fs::write(dir.path().join(".ignore"), "other.txt\n").unwrap();
+        std::fs::write(dir.path().join("secret.txt"), "secret").unwrap();
+        std::fs::write(dir.path().join("other.txt"), "other").unwrap();
+        std::fs::write(dir.path().join("normal.txt"), "normal").unwrap();
+
+        let matcher = build_matcher(None, None, None);
+
+        // --no-vcs-ignore mirrors main()'s `skip_vcs_ignore` branch: an empty
+        // registry, so the discovered .gitignore has no say, but the walk
+        // itself (no_ignore = false) still honors a generic .ignore file.
+        let files = collect_included_files(dir.path(), false, &matcher, &empty_registry());
+        assert!(files.iter().any(|p| p.ends_with("secret.txt")));
+        assert!(!files.iter().any(|p| p.ends_with("other.txt")));
+        assert!(files.iter().any(|p| p.ends_with("normal.txt")));
+    }
+
+    #[test]
+    fn test_no_ignore_flag_skips_both_discovered_and_dot_ignore_files() {
+        let dir = TempDir::new("repo2file-no-ignore").unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "secret.txt\n").unwrap();
+        std::fs::write(dir.path().join(".ignore"), "other.txt\n").unwrap();
+        std::fs::write(dir.path().join("secret.txt"), "secret").unwrap();
+        std::fs::write(dir.path().join("other.txt"), "other").unwrap();
+
+        let matcher = build_matcher(None, None, None);
+
+        // --no-ignore mirrors main()'s behavior of passing both an empty
+        // registry *and* `no_ignore = true`, so neither ignore source applies.
+        let files = collect_included_files(dir.path(), true, &matcher, &empty_registry());
+        assert!(files.iter().any(|p| p.ends_with("secret.txt")));
+        assert!(files.iter().any(|p| p.ends_with("other.txt")));
+    }
+
+    #[test]
+    fn test_no_default_ignore_flag_disables_builtin_ignore_list() {
+        // Mirrors main()'s `no_default_ignore` branch: swap DefaultIgnore::default()
+        // for an empty one instead of threading a CLI flag through Matcher.
+        let with_defaults = Matcher::build(None, None, None, &DefaultIgnore::default()).unwrap();
+        let without_defaults = Matcher::build(
+            None,
+            None,
+            None,
+            &DefaultIgnore {
+                ignore_files: Vec::new(),
+                ignore_dirs: Vec::new(),
+            },
+        )
+        .unwrap();
+
+        let path = PathBuf::from("input/Cargo.lock");
+        assert!(!with_defaults.should_include(&path, &test_root(), &empty_registry()));
+        assert!(without_defaults.should_include(&path, &test_root(), &empty_registry()));
     }
 
     #[test]
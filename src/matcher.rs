@@ -0,0 +1,104 @@
+use crate::default_ignore::DefaultIgnore;
+use crate::ignore_discovery::IgnoreRegistry;
+use crate::pattern::PatternSet;
+use globset::{Glob, GlobMatcher};
+use std::path::Path;
+
+/// Decides whether a walked path should end up in the output, compiled
+/// once from the CLI arguments and default ignore list so the walk itself
+/// never re-parses a glob per file.
+pub struct Matcher {
+    ignore_dirs: Vec<String>,
+    exact_includes: Vec<String>,
+    glob_includes: Vec<GlobMatcher>,
+    patterns: PatternSet,
+}
+
+impl Matcher {
+    /// Builds the matcher once, surfacing a bad `--ignore-files`/`--include-files`
+    /// glob as a `globset::Error` instead of panicking mid-walk.
+    pub fn build(
+        ignore_dirs: Option<&[String]>,
+        ignore_files: Option<&[String]>,
+        include_files: Option<&[String]>,
+        config: &DefaultIgnore,
+    ) -> Result<Matcher, globset::Error> {
+        let mut all_ignore_dirs = config.ignore_dirs.clone();
+        all_ignore_dirs.extend(ignore_dirs.unwrap_or_default().iter().cloned());
+
+        let (exact_includes, glob_entries): (Vec<String>, Vec<String>) = include_files
+            .unwrap_or_default()
+            .iter()
+            .cloned()
+            .partition(|f| !is_glob_pattern(f));
+        let glob_includes = glob_entries
+            .iter()
+            .map(|f| Glob::new(f).map(|g| g.compile_matcher()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut raw_patterns = config.ignore_files.clone();
+        raw_patterns.extend(ignore_files.unwrap_or_default().iter().cloned());
+        let patterns = PatternSet::from_patterns(&raw_patterns)?;
+
+        Ok(Matcher {
+            ignore_dirs: all_ignore_dirs,
+            exact_includes,
+            glob_includes,
+            patterns,
+        })
+    }
+
+    /// Returns whether `path` (found while walking `root`) should be included.
+    pub fn should_include(&self, path: &Path, root: &Path, registry: &IgnoreRegistry) -> bool {
+        // An exact path/filename or directory in --include-files always wins,
+        // even over a discovered .gitignore rule or the default ignore list.
+        if self
+            .exact_includes
+            .iter()
+            .any(|f| matches_exact_include(path, f))
+        {
+            return true;
+        }
+
+        if self
+            .ignore_dirs
+            .iter()
+            .any(|d| path.components().any(|comp| comp.as_os_str() == d.as_str()))
+        {
+            return false;
+        }
+
+        let relative_path = path.strip_prefix(root).unwrap_or(path);
+
+        // Rules discovered from the repo's own .gitignore/.hgignore hierarchy
+        // decide first; a glob-style --include-files entry must not resurrect
+        // a file that is individually gitignored. `registry` scopes its
+        // patterns to each file's own directory, so it is evaluated against
+        // the untouched (non-root-relative) path.
+        let mut decision = registry.evaluate(path);
+
+        // A glob-style include entry overrides the tool's own default ignore-file
+        // patterns and --ignore-files, but not the discovered rules above nor
+        // the ignored directories already rejected earlier.
+        let glob_included = self.glob_includes.iter().any(|g| g.is_match(relative_path));
+
+        // The default ignore-file patterns and --ignore-files only fill in when
+        // the discovered rules above had no opinion; they never overturn a
+        // decision (including a whitelist) that a real .gitignore already made.
+        // Ignored directories are different: they were checked first and
+        // exclude a path whatever the discovered rules say.
+        if decision.is_none() && !glob_included {
+            decision = self.patterns.evaluate(relative_path);
+        }
+
+        !decision.unwrap_or(false)
+    }
+}
+
+fn is_glob_pattern(pattern: &str) -> bool {
+    pattern.contains(['*', '?', '['])
+}
+
+fn matches_exact_include(path: &Path, entry: &str) -> bool {
+    path.ends_with(entry) || path.components().any(|comp| comp.as_os_str() == entry)
+}
@@ -0,0 +1,139 @@
+use globset::{Glob, GlobMatcher};
+use std::path::Path;
+
+/// A single ignore/whitelist rule, following gitignore conventions: a
+/// `!`-prefixed pattern re-includes rather than excludes, and a pattern
+/// containing a `/` is anchored to the root it was declared in instead of
+/// matching against any path component.
+pub struct Pattern {
+    glob: GlobMatcher,
+    is_whitelist: bool,
+    anchored: bool,
+}
+
+impl Pattern {
+    pub fn parse(raw: &str) -> Result<Pattern, globset::Error> {
+        let (is_whitelist, rest) = match raw.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, raw),
+        };
+        let anchored = rest.contains('/');
+        // A leading `/` is the gitignore root-anchor marker, not part of the
+        // glob itself: the path it is matched against never carries one.
+        let rest = rest.strip_prefix('/').unwrap_or(rest);
+        let glob = Glob::new(rest)?.compile_matcher();
+        Ok(Pattern {
+            glob,
+            is_whitelist,
+            anchored,
+        })
+    }
+
+    pub fn is_whitelist(&self) -> bool {
+        self.is_whitelist
+    }
+
+    /// `path` must be relative to the root this pattern was declared in.
+    fn is_match(&self, path: &Path) -> bool {
+        if self.anchored {
+            // A directory-only anchor (e.g. `/build`) must also match every
+            // path beneath it, not just `path` itself.
+            return path.ancestors().any(|ancestor| self.glob.is_match(ancestor));
+        }
+        path.file_name()
+            .is_some_and(|name| self.glob.is_match(name))
+            || path.components().any(|c| self.glob.is_match(c.as_os_str()))
+    }
+}
+
+/// An ordered collection of [`Pattern`]s, evaluated gitignore-style: the
+/// *last* pattern that matches a path decides whether it is ignored or
+/// whitelisted, and a path that nothing matches is left untouched.
+pub struct PatternSet {
+    patterns: Vec<Pattern>,
+}
+
+impl PatternSet {
+    pub fn new() -> Self {
+        PatternSet {
+            patterns: Vec::new(),
+        }
+    }
+
+    pub fn from_patterns<S: AsRef<str>>(raw_patterns: &[S]) -> Result<Self, globset::Error> {
+        let mut set = PatternSet::new();
+        for raw in raw_patterns {
+            set.push(Pattern::parse(raw.as_ref())?);
+        }
+        Ok(set)
+    }
+
+    pub fn push(&mut self, pattern: Pattern) {
+        self.patterns.push(pattern);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Returns `Some(true)` if the last matching pattern ignores `path`,
+    /// `Some(false)` if it whitelists it, or `None` if nothing matched.
+    pub fn evaluate(&self, path: &Path) -> Option<bool> {
+        let mut decision = None;
+        for pattern in &self.patterns {
+            if pattern.is_match(path) {
+                decision = Some(!pattern.is_whitelist());
+            }
+        }
+        decision
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn unanchored_pattern_matches_basename_and_components() {
+        let set = PatternSet::from_patterns(&["*.json"]).unwrap();
+        assert_eq!(set.evaluate(&PathBuf::from("src/data.json")), Some(true));
+        assert_eq!(set.evaluate(&PathBuf::from("src/data.txt")), None);
+    }
+
+    #[test]
+    fn whitelist_pattern_overrides_earlier_ignore() {
+        let set = PatternSet::from_patterns(&["*.json", "!package.json"]).unwrap();
+        assert_eq!(set.evaluate(&PathBuf::from("package.json")), Some(false));
+        assert_eq!(set.evaluate(&PathBuf::from("other.json")), Some(true));
+    }
+
+    #[test]
+    fn last_match_wins_even_over_an_earlier_more_specific_whitelist() {
+        let set = PatternSet::from_patterns(&["!package.json", "*.json"]).unwrap();
+        assert_eq!(set.evaluate(&PathBuf::from("package.json")), Some(true));
+    }
+
+    #[test]
+    fn anchored_pattern_matches_relative_to_root_only() {
+        let set = PatternSet::from_patterns(&["build/output.txt"]).unwrap();
+        assert_eq!(set.evaluate(&PathBuf::from("build/output.txt")), Some(true));
+        assert_eq!(
+            set.evaluate(&PathBuf::from("nested/build/output.txt")),
+            None
+        );
+    }
+
+    #[test]
+    fn leading_slash_anchors_to_root_like_a_bare_slash_pattern() {
+        let set = PatternSet::from_patterns(&["/build"]).unwrap();
+        assert_eq!(set.evaluate(&PathBuf::from("build/out.txt")), Some(true));
+        assert_eq!(set.evaluate(&PathBuf::from("nested/build/out.txt")), None);
+    }
+
+    #[test]
+    fn no_match_returns_none() {
+        let set = PatternSet::from_patterns(&["*.lock"]).unwrap();
+        assert_eq!(set.evaluate(&PathBuf::from("src/main.rs")), None);
+    }
+}